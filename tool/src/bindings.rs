@@ -1,13 +1,41 @@
-use std::{io};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::{io, thread, time::Duration};
 
 use pyo3::prelude::*;
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::types::PyType;
 
+use evdev::{Device, EventType};
 use hidapi::HidApi;
 
 use crate::{Ec, Error, Access, AccessHid};
 
+mod effects;
+mod imaging;
+mod layout;
+mod reactive;
+mod state;
+mod text;
+
+use effects::{Breathing, ColorEffect, Gradient, RainbowWave, Solid};
+use layout::KeyboardLayout;
+use reactive::{key_to_cell, ReactiveState};
+use state::EcState;
+
+/// Default gamma used for the per-controller correction table.
+const DEFAULT_GAMMA: f64 = 2.2;
+
+/// Precompute a 256-entry gamma correction table: `out = round(255 * (i/255)^gamma)`.
+fn gamma_table(gamma: f64) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        *entry = (255.0 * (i as f64 / 255.0).powf(gamma)).round() as u8;
+    }
+    table
+}
+
 fn to_py_err(err: Error) -> PyErr {
     PyRuntimeError::new_err(format!("EC error: {err:?}"))
 }
@@ -16,6 +44,10 @@ fn to_py_hid(err: hidapi::HidError) -> PyErr {
     PyRuntimeError::new_err(format!("hidapi: {err}"))
 }
 
+fn to_py_io(err: io::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("evdev: {err}"))
+}
+
 fn ec_board(ec: &mut Ec<Box<dyn Access>>) -> Result<String, Error> {
     let data_size = unsafe { ec.access().data_size() };
     let mut data = vec![0; data_size];
@@ -40,18 +72,23 @@ pub struct Led {
     #[pyo3(get)]
     color: (u8, u8, u8),
     sync_color: Option<(u8, u8, u8)>,
+    /// Forces a re-send on the next `render_dirty` even if `color` hasn't
+    /// changed since `sync_color`. Set by `FrameBuffer::mark_dirty`, and also
+    /// by every color setter (`set_color_*`, `FrameBuffer::set`,
+    /// `FrameBuffer::fill`), even when the color written is the current one.
+    dirty: bool,
 }
 
 #[pymethods]
 impl Led {
     #[new]
     pub fn new(index: u8, r: u8, g: u8, b: u8) -> Self {
-        Self {index, color: (r, g, b), sync_color: None}
+        Self {index, color: (r, g, b), sync_color: None, dirty: false}
     }
 
     #[classmethod]
     pub fn from_rgb(_cls: Bound<'_, PyType>, index: u8, color: (u8, u8, u8)) -> Self {
-        Self { index, color, sync_color: None}
+        Self { index, color, sync_color: None, dirty: false}
     }
 
     #[classmethod]
@@ -63,11 +100,24 @@ impl Led {
             index,
             color: (r, g, b),
             sync_color: None,
+            dirty: false,
+        }
+    }
+
+    /// Build a `Led` from HSV, with `h` in `[0, 360)` and `s`/`v` in `[0, 1]`.
+    #[classmethod]
+    pub fn from_hsv(_cls: Bound<'_, PyType>, index: u8, h: f64, s: f64, v: f64) -> Self {
+        Self {
+            index,
+            color: hsv_to_rgb(h, s, v),
+            sync_color: None,
+            dirty: false,
         }
     }
 
     pub fn set_color_rgb(&mut self, r: u8, g:u8, b: u8) -> PyResult<()> {
         self.color = (r, g, b);
+        self.dirty = true;
         Ok(())
     }
 
@@ -77,21 +127,63 @@ impl Led {
             ((hex >> 8) & 0xFF) as u8,
             (hex & 0xFF) as u8,
         );
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Set this LED's color from HSV, with `h` in `[0, 360)` and `s`/`v` in `[0, 1]`.
+    pub fn set_color_hsv(&mut self, h: f64, s: f64, v: f64) -> PyResult<()> {
+        self.color = hsv_to_rgb(h, s, v);
+        self.dirty = true;
         Ok(())
     }
 }
 
 impl Led {
-    fn sync(&mut self, ec: &mut Ec<Box<dyn Access>>) -> Result<(), Error> {
+    fn sync(&mut self, ec: &mut Ec<Box<dyn Access>>, gamma: &[u8; 256]) -> Result<(), Error> {
         if self.sync_color != Some(self.color) {
             let (r, g, b) = self.color;
+            let corrected = (
+                gamma[r as usize],
+                gamma[g as usize],
+                gamma[b as usize],
+            );
             unsafe {
-                ec.led_set_color(self.index, r, g, b)?;
+                ec.led_set_color(self.index, corrected.0, corrected.1, corrected.2)?;
             }
             self.sync_color = Some(self.color);
         }
+        self.dirty = false;
         Ok(())
     }
+
+    /// Whether this LED needs to be re-sent: its color differs from the last
+    /// synced color (or it has never been synced), or its `dirty` flag is set.
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.sync_color != Some(self.color)
+    }
+}
+
+/// HSV to RGB using the standard sextant formula. `h` is in `[0, 360)`, `s`
+/// and `v` are in `[0, 1]`.
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let h = h.rem_euclid(360.0);
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r1, g1, b1) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    (
+        ((r1 + m) * 255.0).round() as u8,
+        ((g1 + m) * 255.0).round() as u8,
+        ((b1 + m) * 255.0).round() as u8,
+    )
 }
 
 #[pyclass]
@@ -105,30 +197,43 @@ pub struct FrameBuffer {
     height: u8,
     #[pyo3(get)]
     num_leds: usize,
+    /// For each `led_map` grid cell, the index into the matching (hole-
+    /// filtered) `leds[row]` vector, or `None` for a `255` hole. Lets grid-
+    /// addressed code (images, text) use physical keyboard columns instead
+    /// of the compacted index `leds` itself uses.
+    grid_index: Vec<Vec<Option<usize>>>,
 }
 
 #[pymethods]
 impl FrameBuffer {
     #[new]
     pub fn new(led_map: Vec<Vec<u8>>) -> Self {
-        let leds: Vec<Vec<Led>> = led_map
-            .into_iter()
-            .map(|row| {
-                row.into_iter()
-                    .filter(|&idx| idx != 255)
-                    .map(|idx| Led {
+        let mut leds: Vec<Vec<Led>> = Vec::with_capacity(led_map.len());
+        let mut grid_index: Vec<Vec<Option<usize>>> = Vec::with_capacity(led_map.len());
+        for row in led_map {
+            let mut led_row = Vec::new();
+            let mut index_row = Vec::with_capacity(row.len());
+            for idx in row {
+                if idx == 255 {
+                    index_row.push(None);
+                } else {
+                    index_row.push(Some(led_row.len()));
+                    led_row.push(Led {
                         index: idx,
                         color: (0, 0, 0),
                         sync_color: None,
-                    })
-                    .collect()
-            })
-            .collect();
+                        dirty: false,
+                    });
+                }
+            }
+            grid_index.push(index_row);
+            leds.push(led_row);
+        }
 
         let height = leds.len() as u8;
-        let width = leds.iter().map(|row| row.len()).max().unwrap_or(0) as u8;
+        let width = grid_index.iter().map(|row| row.len()).max().unwrap_or(0) as u8;
         let num_leds = leds.iter().map(|row| row.len()).sum::<usize>();
-        Self { leds, width, height, num_leds }
+        Self { leds, width, height, num_leds, grid_index }
     }
 
     pub fn get(&self, row: usize, col: usize) -> PyResult<Option<Led>> {
@@ -139,7 +244,10 @@ impl FrameBuffer {
         self.leds
             .get_mut(row)
             .and_then(|row_vec| row_vec.get_mut(col))
-            .map(|led| led.color = (r, g, b));
+            .map(|led| {
+                led.color = (r, g, b);
+                led.dirty = true;
+            });
             Ok(())
     }
 
@@ -147,6 +255,7 @@ impl FrameBuffer {
         for row in &mut self.leds {
             for led in row.iter_mut() {
                 led.color = (r, g, b);
+                led.dirty = true;
             }
         }
         Ok(())
@@ -156,6 +265,44 @@ impl FrameBuffer {
         self.fill(0, 0, 0)
     }
 
+    /// Force `(row, col)` to be re-sent on the next `render_dirty`, even if
+    /// its color hasn't changed since the last render.
+    pub fn mark_dirty(&mut self, row: usize, col: usize) -> PyResult<()> {
+        if let Some(led) = self.leds.get_mut(row).and_then(|r| r.get_mut(col)) {
+            led.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Clear every dirty flag without touching the diff against what was
+    /// last sent to the EC.
+    pub fn clear_dirty(&mut self) -> PyResult<()> {
+        for row in &mut self.leds {
+            for led in row.iter_mut() {
+                led.dirty = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a PNG/JPEG/etc. image, resizing it to the grid and writing each
+    /// pixel into the matching `Led`. Set `dither` to spread quantization
+    /// error (Floyd-Steinberg) across neighbors so downscaled artwork keeps
+    /// detail on this tiny grid.
+    #[pyo3(signature = (path, dither=true))]
+    pub fn load_image(&mut self, path: &str, dither: bool) -> PyResult<()> {
+        imaging::load_into(self, path, dither).map_err(PyRuntimeError::new_err)
+    }
+
+    /// Blit `text` into the grid, `x_offset` columns from the left. `bg`
+    /// defaults to clearing the grid first; pass a color to paint a
+    /// background instead.
+    #[pyo3(signature = (text, fg, x_offset, bg=None))]
+    pub fn draw_text(&mut self, text: &str, fg: (u8, u8, u8), x_offset: i32, bg: Option<(u8, u8, u8)>) -> PyResult<()> {
+        text::draw_text(self, text, fg, bg, x_offset);
+        Ok(())
+    }
+
     #[getter]
     fn flat_leds(&self) -> Vec<Led> {
         self.leds.iter().flatten().cloned().collect()
@@ -163,12 +310,124 @@ impl FrameBuffer {
 }
 
 impl FrameBuffer {
-    fn render(&mut self, ec: &mut Ec<Box<dyn Access>>) -> Result<(), Error> {
+    /// Resolve a `(row, col)` in the physical `led_map` grid (holes
+    /// included) to the `Led` it maps to, or `None` if that cell is a `255`
+    /// hole or out of bounds.
+    pub(crate) fn led_at_mut(&mut self, row: usize, col: usize) -> Option<&mut Led> {
+        let idx = (*self.grid_index.get(row)?.get(col)?)?;
+        self.leds.get_mut(row)?.get_mut(idx)
+    }
+
+    fn render(&mut self, ec: &mut Ec<Box<dyn Access>>, gamma: &[u8; 256]) -> Result<(), Error> {
         for row in &mut self.leds {
             for led in row {
-                led.sync(ec)?;
+                led.sync(ec, gamma)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `render`, but only sends LEDs for which `Led::is_dirty` holds,
+    /// saving an EC round trip per clean LED. Returns the number sent. Mirrors
+    /// `Led::sync`: a LED is only marked synced once `led_set_colors` reports
+    /// it actually went out, so a failed or partial flush leaves the
+    /// un-sent LEDs dirty for the next render instead of silently dropping
+    /// the frame.
+    fn render_dirty(&mut self, ec: &mut Ec<Box<dyn Access>>, gamma: &[u8; 256]) -> Result<usize, Error> {
+        let mut dirty_leds = Vec::new();
+        let mut updates = Vec::new();
+        for row in &mut self.leds {
+            for led in row.iter_mut() {
+                if led.is_dirty() {
+                    let (r, g, b) = led.color;
+                    updates.push((led.index, gamma[r as usize], gamma[g as usize], gamma[b as usize]));
+                    dirty_leds.push(led);
+                }
+            }
+        }
+        let (sent, result) = ec.led_set_colors(&updates);
+        for led in dirty_leds.into_iter().take(sent) {
+            led.sync_color = Some(led.color);
+            led.dirty = false;
+        }
+        result?;
+        Ok(sent)
+    }
+}
+
+/// Takes a batch of `(index, r, g, b)` updates for `render_dirty`, issuing
+/// one HID report per update via the single-LED `led_set_color` command.
+/// Nothing in this checkout (no other source file references
+/// `led_set_color`, `data_size`, or `Access` outside this module) exposes a
+/// multi-LED report to pack these into, and the core EC protocol crate that
+/// would define one isn't vendored here to confirm one way or the other —
+/// so this exists as the one place to swap in real packing bounded by
+/// `access().data_size()` if and when such a command is confirmed to exist.
+/// Returns the number of updates written before the first error, alongside
+/// that error if one occurred, so callers can mark exactly the LEDs that
+/// made it out as synced.
+trait BatchedLeds {
+    fn led_set_colors(&mut self, updates: &[(u8, u8, u8, u8)]) -> (usize, Result<(), Error>);
+}
+
+impl BatchedLeds for Ec<Box<dyn Access>> {
+    fn led_set_colors(&mut self, updates: &[(u8, u8, u8, u8)]) -> (usize, Result<(), Error>) {
+        for (sent, &(index, r, g, b)) in updates.iter().enumerate() {
+            if let Err(err) = unsafe { self.led_set_color(index, r, g, b) } {
+                return (sent, Err(err));
             }
         }
+        (updates.len(), Ok(()))
+    }
+}
+
+/// A color effect driven by `EcController::run_animation`. Construct one
+/// with `Effect.solid`, `Effect.breathing`, `Effect.rainbow_wave`, or
+/// `Effect.gradient`.
+#[pyclass]
+pub struct Effect {
+    inner: Box<dyn ColorEffect + Send>,
+}
+
+#[pymethods]
+impl Effect {
+    #[classmethod]
+    pub fn solid(_cls: Bound<'_, PyType>, color: (u8, u8, u8)) -> Self {
+        Self { inner: Box::new(Solid { color }) }
+    }
+
+    #[classmethod]
+    pub fn breathing(_cls: Bound<'_, PyType>, color: (u8, u8, u8), period: f64) -> Self {
+        Self { inner: Box::new(Breathing { color, period }) }
+    }
+
+    #[classmethod]
+    pub fn rainbow_wave(_cls: Bound<'_, PyType>, speed: f64, saturation: f64, value: f64) -> Self {
+        Self { inner: Box::new(RainbowWave { speed, saturation, value }) }
+    }
+
+    #[classmethod]
+    pub fn gradient(_cls: Bound<'_, PyType>, top: (u8, u8, u8), bottom: (u8, u8, u8)) -> Self {
+        Self { inner: Box::new(Gradient { top, bottom }) }
+    }
+}
+
+/// A handle to a running `start_reactive` loop. Unlike `EcController`,
+/// this isn't `unsendable` and doesn't borrow the controller, so its
+/// `stop()` can be called from another Python thread while `start_reactive`
+/// is still blocked in its own thread.
+#[pyclass]
+#[derive(Clone)]
+pub struct ReactiveHandle {
+    running: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl ReactiveHandle {
+    /// End the `start_reactive` loop this handle was obtained from. Takes
+    /// effect on its next frame tick, not instantly.
+    pub fn stop(&self) -> PyResult<()> {
+        self.running.store(false, Ordering::SeqCst);
         Ok(())
     }
 }
@@ -178,67 +437,97 @@ pub struct EcController {
     ec: Ec<Box<dyn Access>>,
     #[pyo3(get)]
     led_map: Vec<Vec<u8>>,
+    /// Per-key evdev codes for `start_reactive`, shaped like `led_map`, from
+    /// the matched `KeyboardLayout::key_map`.
+    key_map: Vec<Vec<Option<u16>>>,
     #[pyo3(get)]
     framebuffer: FrameBuffer,
-    saved_layer_mode: Option<(u8, u8)>,
+    saved_state: Option<EcState>,
+    gamma_table: [u8; 256],
+    reactive_running: Arc<AtomicBool>,
 }
 
 #[pymethods]
 impl EcController {
     #[new]
     pub fn new() -> PyResult<Self> {
-        let ni = 255;
-        let api = HidApi::new().map_err(to_py_hid)?;
-        for info in api.device_list() {
-            match (info.vendor_id(), info.product_id(), info.interface_number()) {
-                // System76 Launch keyboards
-                (0x3384, 0x0001..=0x000A, 1) => {
-                    let device = info.open_device(&api).map_err(to_py_hid)?;
-                    let access = AccessHid::new(device, 10, 100).map_err(to_py_err)?;
-                    let ec = unsafe { Ec::new(access).map_err(to_py_err)? }.into_dyn();
-
-                    //refactor this to set these per keyboard layout based on device info
-                    let led_map = vec![
-                        vec![69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83],
-                        vec![68, 67, 66, 65, 64, 63, 62, 61, 60, 59, 58, 57, 56, 55, 54],
-                        vec![39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53],
-                        vec![38, 37, 36, 35, 34, 33, 32, 31, 30, 29, 28, 27, 26, ni, 25],
-                        vec![12, 11, 10,  9,  8,  7,  6,  5,  4,  3,  2,  1,  0, ni, ni],
-                        vec![13, 14, 15, 16, 17, ni, 18, 19, 20, 21, ni, 22, 23, 24, ni],
-                    ];
-                    let framebuffer = FrameBuffer::new(led_map.clone());
-                    return Ok(Self { ec, led_map, framebuffer, saved_layer_mode: None });
-                }
-                _ => {}
-            }
-        }
+        Self::discover(layout::bundled())
+    }
 
-        Err(PyRuntimeError::new_err("No compatible EC HID device found"))
+    /// Like `EcController()`, but also consider the layout in `path` (RON,
+    /// JSON, or TOML), ahead of the bundled set, so a new System76 board can
+    /// be supported by dropping in a config file instead of a rebuild.
+    #[staticmethod]
+    pub fn with_layout(path: String) -> PyResult<Self> {
+        let user_layout = layout::load_file(Path::new(&path)).map_err(PyRuntimeError::new_err)?;
+        let mut layouts = vec![user_layout];
+        layouts.extend(layout::bundled());
+        Self::discover(layouts)
     }
 
+    /// Names of the layouts `discover`/`with_layout` would search: the
+    /// bundled set, plus the layout at `path` if one is given, so a dropped-in
+    /// config shows up here too instead of only the hardcoded boards.
+    #[staticmethod]
+    #[pyo3(signature = (path=None))]
+    pub fn list_layouts(path: Option<String>) -> PyResult<Vec<String>> {
+        let mut names: Vec<String> = layout::bundled().into_iter().map(|l| l.name).collect();
+        if let Some(path) = path {
+            let user_layout = layout::load_file(Path::new(&path)).map_err(PyRuntimeError::new_err)?;
+            names.insert(0, user_layout.name);
+        }
+        Ok(names)
+    }
+
+    /// Snapshot the EC's current LED state so `close` can restore it, then
+    /// switch every layer into direct-control mode for custom lighting.
     pub fn open(&mut self) -> PyResult<()> {
-        // let (mode, speed) = unsafe { self.ec.led_get_mode(1).map_err(to_py_err)? };
-        // self.saved_layer_mode = Some((mode, speed));
+        self.saved_state = Some(state::capture(&mut self.ec, self.framebuffer.num_leds).map_err(to_py_err)?);
         for layer in 0..4 {
-            println!("Set layer {} mode: {:?}", layer, unsafe {
-                self.ec.led_set_mode(layer, 1, 0)
-            });
-            println!("Set layer {} brightness: {:?}", layer, unsafe {
-                self.ec.led_set_value(0xF0 | layer, 0xFF)
-            });
+            unsafe {
+                self.ec.led_set_mode(layer, 1, 0).map_err(to_py_err)?;
+                self.ec.led_set_value(0xF0 | layer, 0xFF).map_err(to_py_err)?;
+            }
         }
         Ok(())
     }
 
+    /// Restore the LED state captured by `open`, so the keyboard returns to
+    /// its prior appearance.
     pub fn close(&mut self) -> PyResult<()> {
-        // if let Some((mode, speed)) = self.saved_layer_mode.take() {
-            // unsafe {
-            //     self.ec.led_set_mode(1, mode, speed).map_err(to_py_err)?;
-            // }
-        // }
+        if let Some(saved) = self.saved_state.take() {
+            state::restore(&mut self.ec, &saved).map_err(to_py_err)?;
+        }
         Ok(())
     }
 
+    /// Read back the EC's full LED state (every layer's mode/speed and
+    /// brightness, plus every LED's color) without changing anything.
+    pub fn snapshot(&mut self) -> PyResult<EcState> {
+        state::capture(&mut self.ec, self.framebuffer.num_leds).map_err(to_py_err)
+    }
+
+    /// Write a previously captured `EcState` back to the EC.
+    pub fn restore(&mut self, saved: EcState) -> PyResult<()> {
+        state::restore(&mut self.ec, &saved).map_err(to_py_err)
+    }
+
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.open()?;
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+
     #[getter]
     fn board(&mut self) -> PyResult<String> {
         ec_board(&mut self.ec).map_err(to_py_err)
@@ -279,17 +568,233 @@ impl EcController {
             index,
             color: (r, g, b),
             sync_color: None,
+            dirty: false,
         })
     }
 
     pub fn set_led(&mut self, mut led: Led) -> PyResult<()> {
-        led.sync(&mut self.ec).map_err(to_py_err)?;
+        led.sync(&mut self.ec, &self.gamma_table).map_err(to_py_err)?;
         Ok(())
     }
 
     pub fn render_framebuffer(&mut self) -> PyResult<()> {
-        self.framebuffer.render(&mut self.ec).map_err(to_py_err)?;
+        self.framebuffer.render(&mut self.ec, &self.gamma_table).map_err(to_py_err)?;
+        Ok(())
+    }
+
+    /// Like `render_framebuffer`, but only transmits LEDs whose color differs
+    /// from what was last sent, or that were flagged dirty since the last
+    /// render. Any color setter sets that flag, so a LED rewritten with its
+    /// unchanged color is transmitted again. Returns how many were sent.
+    pub fn render_framebuffer_dirty(&mut self) -> PyResult<usize> {
+        self.framebuffer.render_dirty(&mut self.ec, &self.gamma_table).map_err(to_py_err)
+    }
+
+    /// Recompute the gamma correction table applied to LED colors on render.
+    pub fn set_gamma(&mut self, gamma: f64) -> PyResult<()> {
+        self.gamma_table = gamma_table(gamma);
+        Ok(())
+    }
+
+    /// Drive `effect` for `duration` seconds at `fps`, rendering a frame to
+    /// the keyboard on each tick.
+    pub fn run_animation(&mut self, mut effect: PyRefMut<Effect>, fps: f64, duration: f64) -> PyResult<()> {
+        let fps = fps.max(1.0);
+        let frame_time = Duration::from_secs_f64(1.0 / fps);
+        let ticks = (duration * fps).round().max(0.0) as u64;
+        for tick in 0..ticks {
+            let phase = tick as f64 / fps;
+            effect.inner.frame(&mut self.framebuffer, phase);
+            self.framebuffer.render(&mut self.ec, &self.gamma_table).map_err(to_py_err)?;
+            thread::sleep(frame_time);
+        }
         Ok(())
     }
+
+    /// A handle whose `stop()` ends this `start_reactive` loop from another
+    /// Python thread. Grab it before calling `start_reactive` (which
+    /// blocks), since `EcController` itself can't take other calls while
+    /// `start_reactive` holds it.
+    pub fn reactive_handle(&self) -> ReactiveHandle {
+        ReactiveHandle { running: self.reactive_running.clone() }
+    }
+
+    /// Light up keys as they're pressed on `device_path` (e.g.
+    /// `/dev/input/event3`), with a ripple that diffuses to neighboring
+    /// LEDs and fades with distance (`falloff`) and time (`decay`). Blocks
+    /// until a `ReactiveHandle` from `reactive_handle()` is stopped (from
+    /// another Python thread) or the process receives a signal.
+    pub fn start_reactive(
+        &mut self,
+        py: Python<'_>,
+        device_path: String,
+        color: (u8, u8, u8),
+        falloff: f64,
+        decay: f64,
+        fps: f64,
+    ) -> PyResult<()> {
+        let mut device = Device::open(&device_path).map_err(to_py_io)?;
+        device.set_nonblocking(true).map_err(to_py_io)?;
+
+        let state = ReactiveState::default();
+        let frame_time = Duration::from_secs_f64(1.0 / fps.max(1.0));
+        self.reactive_running.store(true, Ordering::SeqCst);
+
+        while self.reactive_running.load(Ordering::SeqCst) {
+            if let Ok(events) = device.fetch_events() {
+                for event in events {
+                    if event.event_type() == EventType::KEY && event.value() == 1 {
+                        if let Some((row, col)) = key_to_cell(&self.key_map, event.code()) {
+                            state.seed(row, col);
+                        }
+                    }
+                }
+            }
+            state.tick(&mut self.framebuffer, color, falloff, decay);
+            self.framebuffer.render(&mut self.ec, &self.gamma_table).map_err(to_py_err)?;
+            // Release the GIL for the sleep so a `ReactiveHandle.stop()` call
+            // waiting on another Python thread actually gets to run.
+            py.allow_threads(|| thread::sleep(frame_time));
+            py.check_signals()?;
+        }
+        Ok(())
+    }
+
+    /// Scroll `text` across the grid right-to-left, `step` columns per
+    /// frame at `fps`, until it has fully passed off the left edge.
+    pub fn scroll_text(&mut self, text: String, color: (u8, u8, u8), fps: f64, step: i32) -> PyResult<()> {
+        let frame_time = Duration::from_secs_f64(1.0 / fps.max(1.0));
+        let step = step.max(1);
+        let mut x_offset = self.framebuffer.width as i32;
+        let end = -text::text_width(&text);
+        while x_offset > end {
+            text::draw_text(&mut self.framebuffer, &text, color, None, x_offset);
+            self.framebuffer.render(&mut self.ec, &self.gamma_table).map_err(to_py_err)?;
+            thread::sleep(frame_time);
+            x_offset -= step;
+        }
+        Ok(())
+    }
+}
+
+impl EcController {
+    /// Scan HID devices for one matching a vendor/product ID in `layouts`,
+    /// then prefer a layout whose `board` matches the EC's reported
+    /// `board()` string exactly, falling back to the first layout that
+    /// only matches on vendor/product ID (firmware `board()` strings are
+    /// sometimes namespaced, e.g. `system76/launch_1`, so a literal
+    /// mismatch shouldn't fail discovery outright).
+    fn discover(layouts: Vec<KeyboardLayout>) -> PyResult<Self> {
+        let api = HidApi::new().map_err(to_py_hid)?;
+        for info in api.device_list() {
+            if info.interface_number() != 1 {
+                continue;
+            }
+            let (vendor_id, product_id) = (info.vendor_id(), info.product_id());
+            let id_matches = |l: &&KeyboardLayout| {
+                l.vendor_id == vendor_id && (l.product_id_min..=l.product_id_max).contains(&product_id)
+            };
+            if !layouts.iter().any(id_matches) {
+                continue;
+            }
+
+            let device = info.open_device(&api).map_err(to_py_hid)?;
+            let access = AccessHid::new(device, 10, 100).map_err(to_py_err)?;
+            let mut ec = unsafe { Ec::new(access).map_err(to_py_err)? }.into_dyn();
+            let board = ec_board(&mut ec).map_err(to_py_err)?;
+
+            let layout = layouts
+                .iter()
+                .find(|l| l.matches(&board, vendor_id, product_id))
+                .or_else(|| layouts.iter().find(id_matches));
+            let Some(layout) = layout else {
+                continue;
+            };
+
+            let led_map = layout.led_map.clone();
+            let key_map = layout.key_map.clone();
+            let framebuffer = FrameBuffer::new(led_map.clone());
+            return Ok(Self {
+                ec,
+                led_map,
+                key_map,
+                framebuffer,
+                saved_state: None,
+                gamma_table: gamma_table(DEFAULT_GAMMA),
+                reactive_running: Arc::new(AtomicBool::new(false)),
+            });
+        }
+
+        Err(PyRuntimeError::new_err("No compatible EC HID device found"))
+    }
+}
+
+impl Drop for EcController {
+    fn drop(&mut self) {
+        if let Some(saved) = self.saved_state.take() {
+            let _ = state::restore(&mut self.ec, &saved);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsv_to_rgb_primary_colors() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(120.0, 1.0, 1.0), (0, 255, 0));
+        assert_eq!(hsv_to_rgb(240.0, 1.0, 1.0), (0, 0, 255));
+    }
+
+    #[test]
+    fn hsv_to_rgb_zero_saturation_is_gray() {
+        assert_eq!(hsv_to_rgb(90.0, 0.0, 0.5), (128, 128, 128));
+    }
+
+    #[test]
+    fn hsv_to_rgb_wraps_hue() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), hsv_to_rgb(360.0, 1.0, 1.0));
+        assert_eq!(hsv_to_rgb(-30.0, 1.0, 1.0), hsv_to_rgb(330.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn gamma_table_endpoints_are_fixed() {
+        let table = gamma_table(DEFAULT_GAMMA);
+        assert_eq!(table[0], 0);
+        assert_eq!(table[255], 255);
+    }
+
+    #[test]
+    fn led_is_dirty_after_color_change() {
+        let mut led = Led::new(0, 0, 0, 0);
+        assert!(!led.is_dirty());
+        led.set_color_rgb(1, 2, 3).unwrap();
+        assert!(led.is_dirty());
+    }
+
+    #[test]
+    fn led_is_dirty_even_with_clean_flag_if_color_drifted_from_sync() {
+        let mut led = Led::new(0, 5, 5, 5);
+        led.dirty = false;
+        led.sync_color = Some((1, 1, 1));
+        assert!(led.is_dirty());
+    }
+
+    #[test]
+    fn led_is_clean_once_synced() {
+        let mut led = Led::new(0, 5, 5, 5);
+        led.dirty = false;
+        led.sync_color = Some(led.color);
+        assert!(!led.is_dirty());
+    }
+
+    #[test]
+    fn gamma_table_darkens_midtones() {
+        // gamma > 1 pulls every non-endpoint value below the identity line.
+        let table = gamma_table(DEFAULT_GAMMA);
+        assert!(table[128] < 128);
+    }
 }
 
@@ -0,0 +1,83 @@
+//! Built-in [`ColorEffect`] implementations driven by `EcController::run_animation`.
+
+use super::FrameBuffer;
+
+/// A per-frame color effect. `frame` is called once per animation tick with
+/// the elapsed time (in seconds) since the animation started, and should
+/// paint the next frame into `fb` in place.
+pub trait ColorEffect {
+    fn frame(&mut self, fb: &mut FrameBuffer, phase: f64);
+}
+
+pub struct Solid {
+    pub color: (u8, u8, u8),
+}
+
+impl ColorEffect for Solid {
+    fn frame(&mut self, fb: &mut FrameBuffer, _phase: f64) {
+        let (r, g, b) = self.color;
+        let _ = fb.fill(r, g, b);
+    }
+}
+
+pub struct Breathing {
+    pub color: (u8, u8, u8),
+    pub period: f64,
+}
+
+impl ColorEffect for Breathing {
+    fn frame(&mut self, fb: &mut FrameBuffer, phase: f64) {
+        let period = if self.period > 0.0 { self.period } else { 1.0 };
+        let t = (phase / period) * 2.0 * std::f64::consts::PI;
+        let level = (t.sin() + 1.0) / 2.0;
+        let (r, g, b) = self.color;
+        let scale = |c: u8| (c as f64 * level).round() as u8;
+        let _ = fb.fill(scale(r), scale(g), scale(b));
+    }
+}
+
+pub struct RainbowWave {
+    pub speed: f64,
+    pub saturation: f64,
+    pub value: f64,
+}
+
+impl ColorEffect for RainbowWave {
+    fn frame(&mut self, fb: &mut FrameBuffer, phase: f64) {
+        let width = fb.width.max(1) as f64;
+        let offset = phase * self.speed * 360.0;
+        let (height, cols) = (fb.height as usize, fb.width as usize);
+        for row in 0..height {
+            for col in 0..cols {
+                let hue = (col as f64 / width * 360.0 + offset).rem_euclid(360.0);
+                if let Some(led) = fb.led_at_mut(row, col) {
+                    led.set_color_hsv(hue, self.saturation, self.value);
+                }
+            }
+        }
+    }
+}
+
+/// A static top-to-bottom gradient across rows.
+pub struct Gradient {
+    pub top: (u8, u8, u8),
+    pub bottom: (u8, u8, u8),
+}
+
+impl ColorEffect for Gradient {
+    fn frame(&mut self, fb: &mut FrameBuffer, _phase: f64) {
+        let last_row = fb.height.saturating_sub(1).max(1) as f64;
+        for (row_idx, row) in fb.leds.iter_mut().enumerate() {
+            let t = row_idx as f64 / last_row;
+            let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            let (r, g, b) = (
+                lerp(self.top.0, self.bottom.0),
+                lerp(self.top.1, self.bottom.1),
+                lerp(self.top.2, self.bottom.2),
+            );
+            for led in row.iter_mut() {
+                let _ = led.set_color_rgb(r, g, b);
+            }
+        }
+    }
+}
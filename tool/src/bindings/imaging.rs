@@ -0,0 +1,104 @@
+//! Load artwork into a [`FrameBuffer`], with optional Floyd-Steinberg
+//! dithering to keep detail on the keyboard's tiny LED grid.
+
+use image::GenericImageView;
+
+use super::FrameBuffer;
+
+/// Resize `path` to `fb`'s grid and write each pixel into the `Led` at that
+/// physical `(y, x)` cell, skipping `255` holes via `FrameBuffer::led_at_mut`
+/// (a compacted `leds` index would shift every column after a hole). When
+/// `dither` is set, each pixel's quantization error is diffused to its
+/// neighbors with Floyd-Steinberg weights (7/16 right, 3/16 below-left,
+/// 5/16 below, 1/16 below-right) so the downscale doesn't crush detail.
+pub fn load_into(fb: &mut FrameBuffer, path: &str, dither: bool) -> Result<(), String> {
+    let (width, height) = (fb.width as u32, fb.height as u32);
+    let img = image::open(path).map_err(|err| err.to_string())?;
+    let resized = img.resize_exact(
+        width.max(1),
+        height.max(1),
+        image::imageops::FilterType::Triangle,
+    );
+
+    let (w, h) = (width as usize, height as usize);
+    let mut pixels: Vec<(f32, f32, f32)> = (0..h)
+        .flat_map(|y| {
+            (0..w).map(move |x| {
+                let p = resized.get_pixel(x as u32, y as u32);
+                (p[0] as f32, p[1] as f32, p[2] as f32)
+            })
+        })
+        .collect();
+
+    for y in 0..h {
+        for x in 0..w {
+            let (old_r, old_g, old_b) = pixels[y * w + x];
+            let (r, g, b) = (
+                old_r.round().clamp(0.0, 255.0),
+                old_g.round().clamp(0.0, 255.0),
+                old_b.round().clamp(0.0, 255.0),
+            );
+            if let Some(led) = fb.led_at_mut(y, x) {
+                let _ = led.set_color_rgb(r as u8, g as u8, b as u8);
+            }
+
+            if dither {
+                let err = (old_r - r, old_g - g, old_b - b);
+                diffuse(&mut pixels, w, h, x, y, 1, 0, 7.0 / 16.0, err);
+                diffuse(&mut pixels, w, h, x, y, -1, 1, 3.0 / 16.0, err);
+                diffuse(&mut pixels, w, h, x, y, 0, 1, 5.0 / 16.0, err);
+                diffuse(&mut pixels, w, h, x, y, 1, 1, 1.0 / 16.0, err);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn diffuse(
+    pixels: &mut [(f32, f32, f32)],
+    w: usize,
+    h: usize,
+    x: usize,
+    y: usize,
+    dx: i32,
+    dy: i32,
+    weight: f32,
+    err: (f32, f32, f32),
+) {
+    let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+    if nx < 0 || ny < 0 || nx as usize >= w || ny as usize >= h {
+        return;
+    }
+    let cell = &mut pixels[ny as usize * w + nx as usize];
+    cell.0 = (cell.0 + err.0 * weight).clamp(0.0, 255.0);
+    cell.1 = (cell.1 + err.1 * weight).clamp(0.0, 255.0);
+    cell.2 = (cell.2 + err.2 * weight).clamp(0.0, 255.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffuse_adds_weighted_error_to_neighbor() {
+        let mut pixels = vec![(0.0, 0.0, 0.0); 4];
+        diffuse(&mut pixels, 2, 2, 0, 0, 1, 0, 7.0 / 16.0, (16.0, 16.0, 16.0));
+        assert_eq!(pixels[1], (7.0, 7.0, 7.0));
+        assert_eq!(pixels[0], (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn diffuse_clamps_to_valid_color_range() {
+        let mut pixels = vec![(250.0, 5.0, 250.0); 4];
+        diffuse(&mut pixels, 2, 2, 0, 0, 1, 0, 1.0, (100.0, -20.0, 100.0));
+        assert_eq!(pixels[1], (255.0, 0.0, 255.0));
+    }
+
+    #[test]
+    fn diffuse_ignores_out_of_bounds_neighbors() {
+        let mut pixels = vec![(1.0, 1.0, 1.0); 4];
+        diffuse(&mut pixels, 2, 2, 1, 1, 1, 0, 1.0, (50.0, 50.0, 50.0));
+        assert_eq!(pixels, vec![(1.0, 1.0, 1.0); 4]);
+    }
+}
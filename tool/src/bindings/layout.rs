@@ -0,0 +1,140 @@
+//! Config-driven keyboard layouts: grid dimensions, `led_map`, and optional
+//! per-key codes, loaded from RON/JSON/TOML instead of being hardcoded as a
+//! matrix literal per board.
+
+use std::path::Path;
+
+use evdev::Key;
+use serde::Deserialize;
+
+/// One keyboard's LED grid, matched against the EC's reported `board()`
+/// string and the HID vendor/product IDs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyboardLayout {
+    pub name: String,
+    pub board: String,
+    pub vendor_id: u16,
+    pub product_id_min: u16,
+    pub product_id_max: u16,
+    pub led_map: Vec<Vec<u8>>,
+    /// Optional per-key evdev `KEY_*` codes, shaped like `led_map`, for the
+    /// reactive lighting mode. `None`/missing cells have no mapped key.
+    #[serde(default)]
+    pub key_map: Vec<Vec<Option<u16>>>,
+}
+
+impl KeyboardLayout {
+    pub fn matches(&self, board: &str, vendor_id: u16, product_id: u16) -> bool {
+        self.board == board
+            && self.vendor_id == vendor_id
+            && (self.product_id_min..=self.product_id_max).contains(&product_id)
+    }
+}
+
+/// Parse a layout from its file extension (`.ron`, `.json`, or `.toml`).
+pub fn load_file(path: &Path) -> Result<KeyboardLayout, String> {
+    let data = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&data).map_err(|err| err.to_string()),
+        Some("toml") => toml::from_str(&data).map_err(|err| err.to_string()),
+        _ => ron::from_str(&data).map_err(|err| err.to_string()),
+    }
+}
+
+/// The layouts shipped with this crate.
+pub fn bundled() -> Vec<KeyboardLayout> {
+    vec![launch_1()]
+}
+
+fn launch_1() -> KeyboardLayout {
+    let ni = 255;
+    KeyboardLayout {
+        name: "launch_1".to_string(),
+        board: "launch_1".to_string(),
+        vendor_id: 0x3384,
+        product_id_min: 0x0001,
+        product_id_max: 0x000A,
+        led_map: vec![
+            vec![69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83],
+            vec![68, 67, 66, 65, 64, 63, 62, 61, 60, 59, 58, 57, 56, 55, 54],
+            vec![39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53],
+            vec![38, 37, 36, 35, 34, 33, 32, 31, 30, 29, 28, 27, 26, ni, 25],
+            vec![12, 11, 10,  9,  8,  7,  6,  5,  4,  3,  2,  1,  0, ni, ni],
+            vec![13, 14, 15, 16, 17, ni, 18, 19, 20, 21, ni, 22, 23, 24, ni],
+        ],
+        key_map: {
+            let nk = None;
+            vec![
+                vec![
+                    Some(Key::KEY_ESC.code()), Some(Key::KEY_F1.code()), Some(Key::KEY_F2.code()),
+                    Some(Key::KEY_F3.code()), Some(Key::KEY_F4.code()), Some(Key::KEY_F5.code()),
+                    Some(Key::KEY_F6.code()), Some(Key::KEY_F7.code()), Some(Key::KEY_F8.code()),
+                    Some(Key::KEY_F9.code()), Some(Key::KEY_F10.code()), Some(Key::KEY_F11.code()),
+                    Some(Key::KEY_F12.code()), Some(Key::KEY_DELETE.code()), Some(Key::KEY_INSERT.code()),
+                ],
+                vec![
+                    Some(Key::KEY_GRAVE.code()), Some(Key::KEY_1.code()), Some(Key::KEY_2.code()),
+                    Some(Key::KEY_3.code()), Some(Key::KEY_4.code()), Some(Key::KEY_5.code()),
+                    Some(Key::KEY_6.code()), Some(Key::KEY_7.code()), Some(Key::KEY_8.code()),
+                    Some(Key::KEY_9.code()), Some(Key::KEY_0.code()), Some(Key::KEY_MINUS.code()),
+                    Some(Key::KEY_EQUAL.code()), Some(Key::KEY_BACKSPACE.code()), Some(Key::KEY_HOME.code()),
+                ],
+                vec![
+                    Some(Key::KEY_TAB.code()), Some(Key::KEY_Q.code()), Some(Key::KEY_W.code()),
+                    Some(Key::KEY_E.code()), Some(Key::KEY_R.code()), Some(Key::KEY_T.code()),
+                    Some(Key::KEY_Y.code()), Some(Key::KEY_U.code()), Some(Key::KEY_I.code()),
+                    Some(Key::KEY_O.code()), Some(Key::KEY_P.code()), Some(Key::KEY_LEFTBRACE.code()),
+                    Some(Key::KEY_RIGHTBRACE.code()), Some(Key::KEY_BACKSLASH.code()), Some(Key::KEY_END.code()),
+                ],
+                vec![
+                    Some(Key::KEY_CAPSLOCK.code()), Some(Key::KEY_A.code()), Some(Key::KEY_S.code()),
+                    Some(Key::KEY_D.code()), Some(Key::KEY_F.code()), Some(Key::KEY_G.code()),
+                    Some(Key::KEY_H.code()), Some(Key::KEY_J.code()), Some(Key::KEY_K.code()),
+                    Some(Key::KEY_L.code()), Some(Key::KEY_SEMICOLON.code()), Some(Key::KEY_APOSTROPHE.code()),
+                    Some(Key::KEY_ENTER.code()), nk, Some(Key::KEY_PAGEDOWN.code()),
+                ],
+                vec![
+                    Some(Key::KEY_LEFTSHIFT.code()), Some(Key::KEY_Z.code()), Some(Key::KEY_X.code()),
+                    Some(Key::KEY_C.code()), Some(Key::KEY_V.code()), Some(Key::KEY_B.code()),
+                    Some(Key::KEY_N.code()), Some(Key::KEY_M.code()), Some(Key::KEY_COMMA.code()),
+                    Some(Key::KEY_DOT.code()), Some(Key::KEY_SLASH.code()), Some(Key::KEY_RIGHTSHIFT.code()),
+                    Some(Key::KEY_UP.code()), nk, nk,
+                ],
+                vec![
+                    Some(Key::KEY_LEFTCTRL.code()), Some(Key::KEY_LEFTMETA.code()), Some(Key::KEY_LEFTALT.code()),
+                    Some(Key::KEY_SPACE.code()), Some(Key::KEY_RIGHTALT.code()), nk,
+                    Some(Key::KEY_RIGHTCTRL.code()), Some(Key::KEY_LEFT.code()), Some(Key::KEY_DOWN.code()),
+                    Some(Key::KEY_RIGHT.code()), nk, Some(Key::KEY_PAGEUP.code()),
+                    Some(Key::KEY_MENU.code()), Some(Key::KEY_COMPOSE.code()), nk,
+                ],
+            ]
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_requires_exact_board_and_id_range() {
+        let layout = launch_1();
+        assert!(layout.matches("launch_1", 0x3384, 0x0001));
+        assert!(!layout.matches("system76/launch_1", 0x3384, 0x0001));
+        assert!(!layout.matches("launch_1", 0x1234, 0x0001));
+        assert!(!layout.matches("launch_1", 0x3384, 0x00FF));
+    }
+
+    #[test]
+    fn bundled_key_map_matches_led_map_hole_shape() {
+        for layout in bundled() {
+            assert_eq!(layout.key_map.len(), layout.led_map.len());
+            for (leds, keys) in layout.led_map.iter().zip(&layout.key_map) {
+                assert_eq!(leds.len(), keys.len());
+                for (&led, key) in leds.iter().zip(keys) {
+                    assert_eq!(led == 255, key.is_none());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Typing-reactive lighting: ripples seeded by evdev keypresses and
+//! diffused across the LED grid over time.
+
+use std::sync::Mutex;
+
+use super::FrameBuffer;
+
+/// A single keypress ripple expanding outward from `(row, col)`.
+struct Ripple {
+    row: usize,
+    col: usize,
+    age: u32,
+}
+
+/// Shared ripple state, seeded by the evdev reader and advanced once per
+/// animation tick.
+#[derive(Default)]
+pub struct ReactiveState {
+    ripples: Mutex<Vec<Ripple>>,
+}
+
+impl ReactiveState {
+    pub fn seed(&self, row: usize, col: usize) {
+        self.ripples.lock().unwrap().push(Ripple { row, col, age: 0 });
+    }
+
+    /// Age every ripple by one tick and paint the result into `fb`: each
+    /// ripple lights its full `falloff` neighborhood at once (see
+    /// `intensity`) rather than expanding outward ring by ring, and fades
+    /// as it ages, with overlapping ripples composited by taking the max
+    /// intensity per cell. Walks the physical grid (not the compacted
+    /// `leds` index) via `FrameBuffer::led_at_mut`, since ripple origins are
+    /// seeded in grid coordinates from `key_to_cell`.
+    pub fn tick(&self, fb: &mut FrameBuffer, color: (u8, u8, u8), falloff: f64, decay: f64) {
+        let mut ripples = self.ripples.lock().unwrap();
+        let _ = fb.clear();
+        let (height, width) = (fb.height as usize, fb.width as usize);
+        for row_idx in 0..height {
+            for col_idx in 0..width {
+                let level = ripples
+                    .iter()
+                    .map(|r| intensity(r, row_idx, col_idx, falloff, decay))
+                    .fold(0.0_f64, f64::max);
+                if level > 0.0 {
+                    if let Some(led) = fb.led_at_mut(row_idx, col_idx) {
+                        let scale = |c: u8| ((c as f64) * level / 255.0).round() as u8;
+                        let _ = led.set_color_rgb(scale(color.0), scale(color.1), scale(color.2));
+                    }
+                }
+            }
+        }
+        for ripple in ripples.iter_mut() {
+            ripple.age += 1;
+        }
+        ripples.retain(|r| (r.age as f64) * decay < 255.0);
+    }
+}
+
+/// `intensity = max(0, 255 - dist*falloff - age*decay)`, `dist` being the
+/// Manhattan distance from the ripple's origin.
+fn intensity(ripple: &Ripple, row: usize, col: usize, falloff: f64, decay: f64) -> f64 {
+    let dist = (ripple.row as i32 - row as i32).unsigned_abs() + (ripple.col as i32 - col as i32).unsigned_abs();
+    (255.0 - dist as f64 * falloff - ripple.age as f64 * decay).max(0.0)
+}
+
+/// Map a Linux `KEY_*` evdev code to a `(row, col)` cell in the grid, using
+/// the active `KeyboardLayout::key_map` (same shape as `led_map`, `None`
+/// cells unmapped) instead of a hardcoded table, so per-board key layouts
+/// stay data-driven like the LED grid itself.
+pub fn key_to_cell(key_map: &[Vec<Option<u16>>], code: u16) -> Option<(usize, usize)> {
+    for (row, keys) in key_map.iter().enumerate() {
+        if let Some(col) = keys.iter().position(|k| *k == Some(code)) {
+            return Some((row, col));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intensity_is_full_at_origin_with_no_age() {
+        let ripple = Ripple { row: 2, col: 3, age: 0 };
+        assert_eq!(intensity(&ripple, 2, 3, 10.0, 10.0), 255.0);
+    }
+
+    #[test]
+    fn intensity_falls_off_with_manhattan_distance() {
+        let ripple = Ripple { row: 0, col: 0, age: 0 };
+        assert_eq!(intensity(&ripple, 0, 3, 10.0, 0.0), 225.0);
+        assert_eq!(intensity(&ripple, 2, 1, 10.0, 0.0), 225.0);
+    }
+
+    #[test]
+    fn intensity_decays_with_age_and_floors_at_zero() {
+        let ripple = Ripple { row: 0, col: 0, age: 100 };
+        assert_eq!(intensity(&ripple, 0, 0, 1.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn key_to_cell_finds_known_key() {
+        use evdev::Key;
+        let key_map = vec![
+            vec![Some(Key::KEY_ESC.code()), None, Some(Key::KEY_F1.code())],
+            vec![Some(Key::KEY_SPACE.code())],
+        ];
+        assert_eq!(key_to_cell(&key_map, Key::KEY_ESC.code()), Some((0, 0)));
+        assert_eq!(key_to_cell(&key_map, Key::KEY_F1.code()), Some((0, 2)));
+        assert_eq!(key_to_cell(&key_map, Key::KEY_SPACE.code()), Some((1, 0)));
+    }
+
+    #[test]
+    fn key_to_cell_unknown_code_is_none() {
+        let key_map = vec![vec![Some(1), None, Some(2)]];
+        assert_eq!(key_to_cell(&key_map, 0xFFFF), None);
+    }
+}
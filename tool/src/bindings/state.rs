@@ -0,0 +1,55 @@
+//! Capturing and restoring the EC's full LED state, so switching into a
+//! custom lighting mode doesn't permanently clobber the firmware's prior
+//! appearance.
+
+use pyo3::prelude::*;
+
+use crate::{Access, Ec, Error};
+
+const LAYERS: u8 = 4;
+
+/// A snapshot of every layer's mode/speed and brightness, plus each LED's
+/// color, as returned by `EcController::snapshot`.
+#[pyclass]
+#[derive(Clone)]
+pub struct EcState {
+    #[pyo3(get)]
+    layers: Vec<(u8, u8)>,
+    #[pyo3(get)]
+    brightness: Vec<u8>,
+    #[pyo3(get)]
+    colors: Vec<(u8, u8, u8)>,
+}
+
+/// Read back each layer's `(mode, speed)` and brightness, and every LED's
+/// current color, from the EC.
+pub fn capture(ec: &mut Ec<Box<dyn Access>>, num_leds: usize) -> Result<EcState, Error> {
+    let mut layers = Vec::with_capacity(LAYERS as usize);
+    let mut brightness = Vec::with_capacity(LAYERS as usize);
+    for layer in 0..LAYERS {
+        layers.push(unsafe { ec.led_get_mode(layer)? });
+        let (value, _) = unsafe { ec.led_get_value(0xF0 | layer)? };
+        brightness.push(value);
+    }
+
+    let mut colors = Vec::with_capacity(num_leds);
+    for index in 0..num_leds as u8 {
+        colors.push(unsafe { ec.led_get_color(index)? });
+    }
+
+    Ok(EcState { layers, brightness, colors })
+}
+
+/// Write a previously captured `EcState` back to the EC.
+pub fn restore(ec: &mut Ec<Box<dyn Access>>, state: &EcState) -> Result<(), Error> {
+    for (layer, &(mode, speed)) in state.layers.iter().enumerate() {
+        unsafe { ec.led_set_mode(layer as u8, mode, speed)?; }
+    }
+    for (layer, &value) in state.brightness.iter().enumerate() {
+        unsafe { ec.led_set_value(0xF0 | layer as u8, value)?; }
+    }
+    for (index, &(r, g, b)) in state.colors.iter().enumerate() {
+        unsafe { ec.led_set_color(index as u8, r, g, b)?; }
+    }
+    Ok(())
+}
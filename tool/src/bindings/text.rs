@@ -0,0 +1,132 @@
+//! A compact 3x5 bitmap font and a scrolling-marquee renderer for the LED
+//! grid.
+
+use super::FrameBuffer;
+
+pub const GLYPH_WIDTH: usize = 3;
+pub const GLYPH_HEIGHT: usize = 5;
+
+/// Bit-packed rows for one glyph, MSB-first within the low `GLYPH_WIDTH`
+/// bits of each byte. Unknown characters render blank.
+fn glyph(ch: char) -> [u8; GLYPH_HEIGHT] {
+    match ch.to_ascii_uppercase() {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b101, 0b111, 0b111, 0b111, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b111, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '?' => [0b111, 0b001, 0b010, 0b000, 0b010],
+        _ => [0b000, 0b000, 0b000, 0b000, 0b000],
+    }
+}
+
+/// Blit `text` into `fb` column-by-column, `x_offset` columns from the
+/// left (may be negative or beyond `fb.width`, so characters can scroll in
+/// from the right and out past the left). Cells outside the grid, and `255`
+/// holes, are resolved (and silently skipped) via `FrameBuffer::led_at_mut`,
+/// which maps physical `(row, col)` grid positions rather than the
+/// compacted `leds` index.
+pub fn draw_text(fb: &mut FrameBuffer, text: &str, fg: (u8, u8, u8), bg: Option<(u8, u8, u8)>, x_offset: i32) {
+    match bg {
+        Some((r, g, b)) => {
+            let _ = fb.fill(r, g, b);
+        }
+        None => {
+            let _ = fb.clear();
+        }
+    }
+
+    let row_offset = ((fb.height as i32 - GLYPH_HEIGHT as i32) / 2).max(0);
+    let mut cursor = x_offset;
+    for ch in text.chars() {
+        let rows = glyph(ch);
+        for (row_idx, bits) in rows.iter().enumerate() {
+            let row = row_offset + row_idx as i32;
+            if row < 0 {
+                continue;
+            }
+            for bit in 0..GLYPH_WIDTH {
+                if (bits >> (GLYPH_WIDTH - 1 - bit)) & 1 == 0 {
+                    continue;
+                }
+                let col = cursor + bit as i32;
+                if col < 0 {
+                    continue;
+                }
+                if let Some(led) = fb.led_at_mut(row as usize, col as usize) {
+                    let _ = led.set_color_rgb(fg.0, fg.1, fg.2);
+                }
+            }
+        }
+        cursor += GLYPH_WIDTH as i32 + 1;
+    }
+}
+
+/// Total column span of `text` once rendered, including inter-glyph spacing.
+pub fn text_width(text: &str) -> i32 {
+    text.chars().count() as i32 * (GLYPH_WIDTH as i32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_width_counts_glyph_plus_spacing() {
+        assert_eq!(text_width(""), 0);
+        assert_eq!(text_width("A"), GLYPH_WIDTH as i32 + 1);
+        assert_eq!(text_width("HI"), 2 * (GLYPH_WIDTH as i32 + 1));
+    }
+
+    #[test]
+    fn glyph_is_case_insensitive() {
+        assert_eq!(glyph('a'), glyph('A'));
+    }
+
+    #[test]
+    fn glyph_unknown_char_is_blank() {
+        assert_eq!(glyph('%'), [0b000, 0b000, 0b000, 0b000, 0b000]);
+    }
+
+    #[test]
+    fn glyph_rows_fit_in_glyph_width_bits() {
+        for ch in "ABCZ019!.?".chars() {
+            for row in glyph(ch) {
+                assert_eq!(row & !((1 << GLYPH_WIDTH) - 1), 0);
+            }
+        }
+    }
+}